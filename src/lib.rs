@@ -1,10 +1,12 @@
 use std::fmt::Debug;
-use std::path::{Path};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use once_cell::sync::{OnceCell};
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
-use tokio::sync::{RwLock, RwLockReadGuard};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{watch, RwLock, RwLockReadGuard};
 
 /*
 The artifacts module provides a generic Artifact<T> struct for managing shared read/write access to data stored in a JSON file.
@@ -15,9 +17,10 @@ The ARTIFACTS_PATH environment variable is used to locate the JSON file and defa
 
 Usage example:
 
-1. Define your struct and make sure it implements DeserializeOwned:
+1. Define your struct and make sure it implements Serialize and DeserializeOwned
+   (Serialize is required so that `update()` can write the new value back to disk):
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct ExampleStruct {
     field1: String,
     field2: u32,
@@ -34,8 +37,10 @@ async fn main() {
     // Initialize the EXAMPLE_LIST data
     EXAMPLE_LIST.init("example.json").await.unwrap();
 
-    // Spawn a background task to watch and reload the EXAMPLE_LIST data every 6 hours
-    tokio::spawn(EXAMPLE_LIST.watch("example.json".to_string(), 6 * 60 * 60));
+    // Spawn a background task to watch and reload the EXAMPLE_LIST data every 6 hours.
+    // Pass `None` to retry transient errors indefinitely, or `Some(n)` to give up after
+    // n consecutive failures.
+    tokio::spawn(EXAMPLE_LIST.watch("example.json".to_string(), 6 * 60 * 60, None));
 
     {
         // Example of getting the data
@@ -56,31 +61,189 @@ async fn main() {
 // concurrent reads while providing exclusive access for updates.
 pub struct Artifact<T> {
     data: OnceCell<RwLock<T>>,
+    path: OnceCell<PathBuf>,
+    migrations: OnceCell<Vec<Migration>>,
+    change: OnceCell<watch::Sender<()>>,
+    watch_status: OnceCell<Arc<WatchStatus>>,
 }
 
-impl<T: Debug + serde::de::DeserializeOwned + Send + Sync + 'static> Artifact<T> {
+// The multiple of `interval_secs` that the `watch()` backoff is capped at.
+const MAX_BACKOFF_MULTIPLIER: u32 = 8;
+
+// Tracks the health of a running `watch()` loop so callers can observe prolonged outages
+// without the watcher itself giving up and dying.
+#[derive(Default)]
+pub struct WatchStatus {
+    consecutive_failures: AtomicU32,
+    last_error: Mutex<Option<String>>,
+}
+
+impl WatchStatus {
+    // The number of reload attempts that have failed in a row since the last successful reload.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::Relaxed)
+    }
+
+    // The most recent reload error, if the watcher is currently in a failing streak.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    fn record_failure(&self, err: String) -> u32 {
+        *self.last_error.lock().unwrap() = Some(err);
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = None;
+    }
+}
+
+// A single schema migration step: takes the raw JSON of the previous version and returns the
+// raw JSON of the next version. Migrations are applied in registration order, one per version
+// bump, so the Nth migration turns version N-1 into version N.
+pub type Migration = Box<dyn Fn(serde_json::Value) -> Result<serde_json::Value, ArtifactError> + Send + Sync>;
+
+impl<T: Debug + serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static> Artifact<T> {
     // Creates a new, uninitialized Artifact instance.
     pub const fn new() -> Self {
         Artifact {
             data: OnceCell::new(),
+            path: OnceCell::new(),
+            migrations: OnceCell::new(),
+            change: OnceCell::new(),
+            watch_status: OnceCell::new(),
+        }
+    }
+
+    // Returns a handle to this Artifact's watch health, creating it if no `watch()` loop has
+    // started yet. Useful for alerting on a watcher that's stuck retrying a broken file.
+    pub fn watch_status(&self) -> Arc<WatchStatus> {
+        self.watch_status.get_or_init(|| Arc::new(WatchStatus::default())).clone()
+    }
+
+    // Subscribes to notifications of the Artifact's value changing, whether from a local
+    // `update()` or a `watch()` reload picking up a new file. The receiver only carries a
+    // signal, not the value itself — callers should `get()` after it fires.
+    pub fn subscribe(&self) -> watch::Receiver<()> {
+        self.change.get_or_init(|| watch::channel(()).0).subscribe()
+    }
+
+    // Notifies any subscribers that the value has changed. A no-op if nobody has subscribed yet.
+    fn notify_changed(&self) {
+        if let Some(tx) = self.change.get() {
+            tx.send_replace(());
         }
     }
 
+    // Registers the ordered chain of schema migrations for this Artifact. Must be called
+    // before `init`/`watch` actually load the file. The current schema version is implicitly
+    // `migrations.len()`: the Nth migration takes a file stored at version N-1 to version N.
+    pub fn register_migrations(&self, migrations: Vec<Migration>) {
+        self.migrations.set(migrations).ok();
+    }
+
+    // The schema version this Artifact expects data to be at, i.e. the number of registered
+    // migrations. An Artifact with no migrations registered is always at version 0.
+    fn current_version(&self) -> u64 {
+        self.migrations.get().map(|m| m.len() as u64).unwrap_or(0)
+    }
+
     // Initializes the Artifact data by loading it from the specified JSON file.
     // This method can be called multiple times, but the data will be initialized only once.
     // If the data is already initialized, this method does nothing.
+    //
+    // This is a thin wrapper around `init_from_reader` that opens the file at
+    // `ARTIFACTS_PATH`/`artifact_file` and additionally remembers the path, so that a later
+    // `update()` knows where to persist to and a migrated file can be rewritten in place.
     pub async fn init(&self, artifact_file: &str) -> Result<(), ArtifactError> {
         if self.data.get().is_none() {
             let artifacts_path = get_env_or_default("ARTIFACTS_PATH", "artifacts".to_string());
             let path = Path::new(&artifacts_path).join(artifact_file);
 
-            let data = get_data::<T>(&path).await.map_err(|err| ArtifactError::InitializationError(err.to_string()))?;
+            let file = File::open(&path).await.map_err(|err| ArtifactError::InitializationError(err.to_string()))?;
 
-            self.data.set(RwLock::new(data)).unwrap();
+            let migrated_value = self.init_from_reader_inner(file).await
+                .map_err(|err| ArtifactError::InitializationError(err.to_string()))?;
+
+            self.path.set(path.clone()).ok();
+
+            // If any migration ran, rewrite the file with the bumped version so future loads
+            // don't have to migrate it again.
+            if let Some(migrated_value) = migrated_value {
+                persist_data(&path, &migrated_value).await?;
+            }
         }
         Ok(())
     }
 
+    // Initializes the Artifact data by reading a JSON document from an arbitrary `AsyncRead`
+    // source instead of a filesystem path — e.g. an HTTP response body, a decompressor, or an
+    // in-memory buffer. Like `init`, this does nothing if the data is already initialized.
+    //
+    // An Artifact initialized this way has no backing file path, so `update()` will be unable
+    // to persist further changes; use `init` for that.
+    pub async fn init_from_reader<R: AsyncRead + Unpin>(&self, reader: R) -> Result<(), ArtifactError> {
+        self.init_from_reader_inner(reader).await.map(|_| ())
+    }
+
+    // Shared engine behind `init` and `init_from_reader`: reads `reader` to completion, applies
+    // schema migrations, and stores the result. Returns the migrated raw value when at least
+    // one migration ran, so `init` can rewrite its backing file with the bumped version.
+    async fn init_from_reader_inner<R: AsyncRead + Unpin>(&self, reader: R) -> Result<Option<serde_json::Value>, ArtifactError> {
+        if self.data.get().is_none() {
+            let contents = read_to_string_chunked(reader).await?;
+            let (data, migrated_value) = self.migrate_contents(&contents)?;
+            self.data.set(RwLock::new(data)).unwrap();
+            Ok(migrated_value)
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Reads `path` as raw JSON and applies any registered schema migrations.
+    // Used by `watch()` to reload the file on each interval.
+    async fn load_and_migrate(&self, path: &Path) -> Result<(T, Option<serde_json::Value>), ArtifactError> {
+        let file = File::open(path).await.map_err(ArtifactError::IoError)?;
+        let contents = read_to_string_chunked(file).await?;
+        self.migrate_contents(&contents)
+    }
+
+    // Parses `contents` as raw JSON, applies any registered migrations from its stored
+    // `version` (defaulting to 0) up to the current version, and only then deserializes the
+    // result into `T`. Returns the migrated raw value alongside `T` when at least one migration
+    // ran, so the caller can rewrite the file with the bumped version.
+    //
+    // Note: `T` must not use `#[serde(deny_unknown_fields)]` — the on-disk `version` field is
+    // left in the JSON object passed to `serde_json::from_value`, and a deny-unknown-fields `T`
+    // would reject it.
+    fn migrate_contents(&self, contents: &str) -> Result<(T, Option<serde_json::Value>), ArtifactError> {
+        let mut value: serde_json::Value = serde_json::from_str(contents).map_err(ArtifactError::SerdeError)?;
+
+        let stored_version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let mut migrated = false;
+
+        if let Some(migrations) = self.migrations.get() {
+            for migration in migrations.iter().skip(stored_version) {
+                value = migration(value)?;
+                migrated = true;
+            }
+
+            if migrated {
+                if let serde_json::Value::Object(ref mut map) = value {
+                    map.insert("version".to_string(), serde_json::Value::from(migrations.len() as u64));
+                } else {
+                    return Err(ArtifactError::MigrationError("migrated value is not a JSON object".to_string()));
+                }
+            }
+        }
+
+        let migrated_value = if migrated { Some(value.clone()) } else { None };
+        let data: T = serde_json::from_value(value).map_err(ArtifactError::SerdeError)?;
+        Ok((data, migrated_value))
+    }
+
     // Provides read access to the Artifact data.
     // This method returns a read guard, which allows multiple concurrent reads.
     pub async fn get(&self) -> Result<RwLockReadGuard<'_, T>, ArtifactError> {
@@ -88,48 +251,203 @@ impl<T: Debug + serde::de::DeserializeOwned + Send + Sync + 'static> Artifact<T>
         Ok(data_lock.read().await)
     }
 
-    // Updates the Artifact data with the provided new_data.
+    // Updates the Artifact data with the provided new_data and persists it to disk.
     // This method provides exclusive write access to the data, blocking other reads and writes
-    // while the update is in progress.
+    // while the update is in progress. The write lock is held across both the disk write and the
+    // in-memory swap, so the two commit together: concurrent `update()` calls can never have the
+    // file end up in one order and memory in the other. The new value is written back to the
+    // file it was initialized from using a crash-safe temp-file-plus-rename, so `update` is the
+    // single source of truth and the `watch` task will never clobber it with a stale reload. Any
+    // `subscribe()` receivers are notified once the new value is in place.
+    //
+    // Returns `ArtifactError::PersistError` if this Artifact was initialized via
+    // `init_from_reader` rather than `init`, since there's no backing file path to write to.
+    //
+    // The on-disk `version` field is only added once migrations are actually registered
+    // (`current_version() > 0`); with none registered, `new_data` is persisted as-is. This
+    // matters for any `T` that doesn't serialize to a JSON object — e.g. `Artifact<Vec<_>>` —
+    // since `#[serde(flatten)]`ing a version field onto it would fail to serialize. Such a `T`
+    // can still be used with `update()`, just not together with `register_migrations`.
     pub async fn update(&self, new_data: T) -> Result<(), ArtifactError> {
         let data_lock = self.data.get().expect("Artifact is not initialized");
+        let path = self.path.get().ok_or_else(|| {
+            ArtifactError::PersistError("no backing path; initialized from a reader".to_string())
+        })?;
+
         let mut data = data_lock.write().await;
+
+        let version = self.current_version();
+        if version > 0 {
+            persist_data(path, &VersionedData { version, data: &new_data }).await?;
+        } else {
+            persist_data(path, &new_data).await?;
+        }
+
         *data = new_data;
+        drop(data);
+
+        self.notify_changed();
 
         Ok(())
     }
 
+    // Replaces the in-memory value and notifies subscribers, without touching disk. Used by
+    // `watch()` to apply a reload it just read from the file it's watching — writing that same
+    // data straight back out would be redundant, since `update()` (the only method that persists)
+    // is reserved for callers actually producing a new value to save.
+    async fn set_in_memory(&self, new_data: T) {
+        let data_lock = self.data.get().expect("Artifact is not initialized");
+        let mut data = data_lock.write().await;
+        *data = new_data;
+        drop(data);
+
+        self.notify_changed();
+    }
+
+    // Compares a freshly reloaded value against what's currently stored. Compared via their JSON
+    // representation rather than requiring `T: PartialEq`, since that bound isn't otherwise needed
+    // anywhere else on `Artifact<T>`.
+    async fn has_changed(&self, new_data: &T) -> bool {
+        let data_lock = self.data.get().expect("Artifact is not initialized");
+        let current = data_lock.read().await;
+        serde_json::to_value(&*current).ok() != serde_json::to_value(new_data).ok()
+    }
+
     // Starts a task that periodically reloads the Artifact data from the specified JSON file.
-    // The task runs indefinitely, reloading the data at the specified interval in seconds.
-    pub async fn watch(&self, artifact_file: String, interval_secs: u64) -> Result<(), ArtifactError> {
+    // The task runs indefinitely, reloading the data at the specified interval in seconds. A
+    // reload that parses but matches what's already stored is a no-op: the in-memory value is
+    // only replaced, and `subscribe()` receivers only notified, when the file's content actually
+    // changed. Otherwise a reload never writes back to disk — it was just read from that same
+    // file, so writing it straight back out would be pure write amplification; `update()` remains
+    // the only path that persists new values. The one exception is a reload that triggers a
+    // schema migration: like `init`, the file is rewritten with the bumped version (reusing the
+    // same atomic-write path) so the migration isn't re-run on every subsequent reload.
+    //
+    // A failed reload (missing file, mid-write truncation, bad JSON) is treated as recoverable:
+    // it's recorded on `watch_status()`, and the loop backs off exponentially from
+    // `interval_secs` up to `MAX_BACKOFF_MULTIPLIER` times that before retrying, all the while
+    // continuing to serve the last-known-good value. Pass `max_consecutive_failures` to give up
+    // and return `ArtifactError::WatchError` after that many reload attempts fail in a row;
+    // `None` retries forever.
+    pub async fn watch(&self, artifact_file: String, interval_secs: u64, max_consecutive_failures: Option<u32>) -> Result<(), ArtifactError> {
         let artifacts_path = get_env_or_default("ARTIFACTS_PATH", "artifacts".to_string());
         let path = Path::new(&artifacts_path).join(artifact_file);
+        let status = self.watch_status();
+
+        let base_interval = Duration::from_secs(interval_secs);
+        let max_backoff = base_interval * MAX_BACKOFF_MULTIPLIER;
+        let mut backoff = base_interval;
 
         loop {
-            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+            tokio::time::sleep(backoff).await;
+
+            match self.load_and_migrate(&path).await {
+                Ok((new_data, migrated_value)) => {
+                    status.record_success();
+                    backoff = base_interval;
 
-            match get_data::<T>(&path).await {
-                Ok(new_data) => {
-                    if let Err(e) = self.update(new_data).await {
-                        return Err(ArtifactError::UpdateError(e.to_string()));
+                    // If the reload ran a migration, rewrite the file with the bumped version so
+                    // the next reload doesn't have to migrate it all over again.
+                    if let Some(migrated_value) = migrated_value {
+                        persist_data(&path, &migrated_value).await?;
+                    }
+
+                    if self.has_changed(&new_data).await {
+                        self.set_in_memory(new_data).await;
                     }
                 }
                 Err(e) => {
-                    return Err(ArtifactError::WatchError(e.to_string()));
+                    let failures = status.record_failure(e.to_string());
+                    backoff = std::cmp::min(backoff * 2, max_backoff);
+
+                    if let Some(max) = max_consecutive_failures {
+                        if failures >= max {
+                            return Err(ArtifactError::WatchError(format!(
+                                "giving up after {} consecutive failures, last error: {}",
+                                failures, e
+                            )));
+                        }
+                    }
                 }
             }
         }
     }
 }
 
-// A helper function to read and deserialize data from a JSON file.
-// The function is generic over the type T, which must implement the DeserializeOwned trait.
-async fn get_data<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, ArtifactError> {
-    let mut file = File::open(path).await.map_err(|err| ArtifactError::IoError(err))?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).await.map_err(|err| ArtifactError::IoError(err))?;
-    let data: T = serde_json::from_str(&contents).map_err(|err| ArtifactError::SerdeError(err))?;
-    Ok(data)
+// The size of each chunk read from an `AsyncRead` source in `read_to_string_chunked`.
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+// Reads `reader` to completion in fixed-size chunks rather than assuming a seekable,
+// size-known source, so callers can feed in a file, an HTTP response body, a decompressor, or
+// any other `AsyncRead` stream.
+async fn read_to_string_chunked<R: AsyncRead + Unpin>(mut reader: R) -> Result<String, ArtifactError> {
+    let mut buf = [0u8; READ_CHUNK_SIZE];
+    let mut accumulator = Vec::new();
+
+    loop {
+        let bytes_read = reader.read(&mut buf).await.map_err(ArtifactError::IoError)?;
+        if bytes_read == 0 {
+            break;
+        }
+        accumulator.extend_from_slice(&buf[..bytes_read]);
+    }
+
+    String::from_utf8(accumulator).map_err(|err| ArtifactError::InitializationError(err.to_string()))
+}
+
+// Wraps a reference to `T` with a top-level `version` field via `#[serde(flatten)]`, so the
+// on-disk JSON carries its schema version without `T` itself needing to know about it.
+#[derive(serde::Serialize)]
+struct VersionedData<'a, T> {
+    version: u64,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+// Serializes `data` to JSON and writes it to `path` using a crash-safe temp-file-plus-rename
+// sequence: the new contents land in a sibling `<file>.tmp-<pid>-<counter>` file created with
+// `create_new` (so concurrent writers, even in the same process, can't collide), are flushed
+// and fsynced, and are only then renamed over `path`. Readers (and the `watch` task) therefore
+// never observe a partially-written file.
+async fn persist_data<T: serde::Serialize>(path: &Path, data: &T) -> Result<(), ArtifactError> {
+    let json = serde_json::to_vec_pretty(data).map_err(ArtifactError::SerdeError)?;
+
+    let file_name = path.file_name().ok_or_else(|| {
+        ArtifactError::PersistError(format!("path {:?} has no file name", path))
+    })?;
+    // The pid alone isn't enough to disambiguate: two concurrent `update()` calls in the same
+    // process (e.g. on a multi-threaded runtime) would otherwise race for the same temp path.
+    // A process-wide counter makes every call's temp file unique.
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let tmp_file_name = format!(
+        "{}.tmp-{}-{}",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+    );
+    let tmp_path = path.with_file_name(tmp_file_name);
+
+    let mut tmp_file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp_path)
+        .await
+        .map_err(|err| ArtifactError::PersistError(err.to_string()))?;
+
+    let write_result = async {
+        tmp_file.write_all(&json).await?;
+        tmp_file.flush().await?;
+        tmp_file.sync_all().await
+    }.await;
+
+    if let Err(err) = write_result {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(ArtifactError::PersistError(err.to_string()));
+    }
+
+    tokio::fs::rename(&tmp_path, path).await.map_err(|err| ArtifactError::PersistError(err.to_string()))?;
+
+    Ok(())
 }
 
 pub fn get_env_or_default(key: &str, default: String) -> String {
@@ -143,6 +461,8 @@ pub enum ArtifactError {
     InitializationError(String),
     UpdateError(String),
     WatchError(String),
+    PersistError(String),
+    MigrationError(String),
 }
 
 impl std::fmt::Display for ArtifactError {
@@ -153,6 +473,8 @@ impl std::fmt::Display for ArtifactError {
             ArtifactError::InitializationError(msg) => write!(f, "Initialization error: {}", msg),
             ArtifactError::UpdateError(msg) => write!(f, "Update error: {}", msg),
             ArtifactError::WatchError(msg) => write!(f, "Watch error: {}", msg),
+            ArtifactError::PersistError(msg) => write!(f, "Persist error: {}", msg),
+            ArtifactError::MigrationError(msg) => write!(f, "Migration error: {}", msg),
         }
     }
 }
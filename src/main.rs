@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use artifacts_crate::Artifact;
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ExampleStruct {
     field1: String,
     field2: u32,
@@ -17,7 +17,7 @@ async fn main() {
     // we need to create the artifacts directory and the example.json file before we start the program
     EXAMPLE_LIST.init("example.json").await.unwrap();
 
-    tokio::spawn(EXAMPLE_LIST.watch("example.json".to_string(), 2));
+    tokio::spawn(EXAMPLE_LIST.watch("example.json".to_string(), 2, None));
 
     {
         let example_data = EXAMPLE_LIST.get().await.unwrap();
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use tempfile::tempdir;
 use tokio::sync::RwLock;
 
-use artifacts_crate::Artifact;
+use artifacts_crate::{Artifact, ArtifactError};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ExampleStruct {
@@ -35,6 +35,38 @@ async fn test_init() {
     );
 }
 
+#[tokio::test]
+async fn test_init_from_reader() {
+    let json = br#"{"field1": "Test string", "field2": 123}"#;
+
+    let example_list = Arc::new(Artifact::<ExampleStruct>::new());
+    example_list.init_from_reader(&json[..]).await.unwrap();
+    let data = example_list.get().await.unwrap();
+    assert_eq!(
+        *data,
+        ExampleStruct {
+            field1: "Test string".to_string(),
+            field2: 123,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_update_without_backing_path_fails() {
+    let json = br#"{"field1": "Test string", "field2": 123}"#;
+
+    let example_list = Arc::new(Artifact::<ExampleStruct>::new());
+    example_list.init_from_reader(&json[..]).await.unwrap();
+
+    let new_data = ExampleStruct {
+        field1: "Updated string".to_string(),
+        field2: 456,
+    };
+    let result = example_list.update(new_data).await;
+
+    assert!(matches!(result, Err(ArtifactError::PersistError(_))));
+}
+
 #[tokio::test]
 async fn test_update() {
     let tmp_dir = tempdir().unwrap();
@@ -101,6 +133,156 @@ async fn test_concurrent_read_and_update() {
     futures::future::join_all(tasks).await;
 }
 
+pub static EXAMPLE_LIST_MT: Artifact<ExampleStruct> = Artifact::new();
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_update_on_multi_thread_runtime() {
+    let tmp_dir = tempdir().unwrap();
+    let artifact_file_path = tmp_dir.path().join("example_concurrent_mt.json");
+    std::fs::write(
+        &artifact_file_path,
+        r#"{"field1": "Initial string", "field2": 1}"#,
+    )
+        .unwrap();
+
+    EXAMPLE_LIST_MT.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+    let num_tasks = 200;
+    let mut tasks = Vec::with_capacity(num_tasks);
+
+    for i in 0..num_tasks {
+        tasks.push(tokio::spawn(async move {
+            let new_data = ExampleStruct {
+                field1: format!("Updated string {}", i),
+                field2: i as u32,
+            };
+            EXAMPLE_LIST_MT.update(new_data).await
+        }));
+    }
+
+    let results = futures::future::join_all(tasks).await;
+    for result in results {
+        result.unwrap().unwrap();
+    }
+
+    // Whichever `update()` call committed last, the file and the in-memory value must agree:
+    // the write lock held across persist-then-swap rules out the disk and memory landing on
+    // two different calls' data.
+    let in_memory = EXAMPLE_LIST_MT.get().await.unwrap().clone();
+    let on_disk: ExampleStruct = serde_json::from_str(&std::fs::read_to_string(&artifact_file_path).unwrap()).unwrap();
+    assert_eq!(in_memory, on_disk);
+}
+
+#[tokio::test]
+async fn test_update_persists_to_disk() {
+    let tmp_dir = tempdir().unwrap();
+    let artifact_file_path = tmp_dir.path().join("example.json");
+    std::fs::write(
+        &artifact_file_path,
+        r#"{"field1": "Test string", "field2": 123}"#,
+    )
+        .unwrap();
+
+    let example_list = Arc::new(Artifact::<ExampleStruct>::new());
+    example_list.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+    let new_data = ExampleStruct {
+        field1: "Persisted string".to_string(),
+        field2: 789,
+    };
+    example_list.update(new_data.clone()).await.unwrap();
+
+    let contents = std::fs::read_to_string(&artifact_file_path).unwrap();
+    let on_disk: ExampleStruct = serde_json::from_str(&contents).unwrap();
+    assert_eq!(on_disk, new_data);
+
+    // No leftover temp file should remain after the rename.
+    let tmp_entries: Vec<_> = std::fs::read_dir(tmp_dir.path())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".tmp-"))
+        .collect();
+    assert!(tmp_entries.is_empty());
+}
+
+#[tokio::test]
+async fn test_update_persists_non_object_root_without_migrations() {
+    let tmp_dir = tempdir().unwrap();
+    let artifact_file_path = tmp_dir.path().join("list.json");
+    std::fs::write(&artifact_file_path, r#"[1, 2, 3]"#).unwrap();
+
+    let example_list = Arc::new(Artifact::<Vec<u32>>::new());
+    example_list.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+    example_list.update(vec![4, 5, 6]).await.unwrap();
+
+    let contents = std::fs::read_to_string(&artifact_file_path).unwrap();
+    let on_disk: Vec<u32> = serde_json::from_str(&contents).unwrap();
+    assert_eq!(on_disk, vec![4, 5, 6]);
+}
+
+#[tokio::test]
+async fn test_subscribe_notified_on_update() {
+    let tmp_dir = tempdir().unwrap();
+    let artifact_file_path = tmp_dir.path().join("example.json");
+    std::fs::write(
+        &artifact_file_path,
+        r#"{"field1": "Test string", "field2": 123}"#,
+    )
+        .unwrap();
+
+    let example_list = Arc::new(Artifact::<ExampleStruct>::new());
+    example_list.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+    let mut changes = example_list.subscribe();
+
+    let new_data = ExampleStruct {
+        field1: "Updated string".to_string(),
+        field2: 456,
+    };
+    example_list.update(new_data).await.unwrap();
+
+    changes.changed().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_init_migrates_old_schema_and_rewrites_version() {
+    let tmp_dir = tempdir().unwrap();
+    let artifact_file_path = tmp_dir.path().join("versioned.json");
+    // No "version" field at all, i.e. version 0: field1 used to be "name".
+    std::fs::write(
+        &artifact_file_path,
+        r#"{"name": "Test string", "field2": 123}"#,
+    )
+        .unwrap();
+
+    let example_list = Artifact::<ExampleStruct>::new();
+    example_list.register_migrations(vec![Box::new(|mut value| {
+        if let Some(name) = value.get_mut("name").map(|v| v.take()) {
+            value.as_object_mut().unwrap().insert("field1".to_string(), name);
+            value.as_object_mut().unwrap().remove("name");
+        }
+        Ok(value)
+    })]);
+    example_list.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+    let data = example_list.get().await.unwrap();
+    assert_eq!(
+        *data,
+        ExampleStruct {
+            field1: "Test string".to_string(),
+            field2: 123,
+        }
+    );
+    drop(data);
+
+    // The migrated file should have been rewritten with the bumped version so it doesn't
+    // need to be migrated again on the next load.
+    let contents = std::fs::read_to_string(&artifact_file_path).unwrap();
+    let on_disk: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    assert_eq!(on_disk.get("version").and_then(|v| v.as_u64()), Some(1));
+}
+
 #[tokio::test]
 async fn test_init_missing_file() {
     let tmp_dir = tempdir().unwrap();
@@ -147,7 +329,7 @@ mod test {
         };
         std::fs::write(&artifact_file_path, serde_json::to_string(&new_data).unwrap()).unwrap();
 
-        let watch_handle = tokio::spawn(EXAMPLE_LIST_2.watch(artifact_file_path.to_str().unwrap().to_string(), 1));
+        let watch_handle = tokio::spawn(EXAMPLE_LIST_2.watch(artifact_file_path.to_str().unwrap().to_string(), 1, None));
 
         // Add a delay to give the watch task enough time to pick up the changes
         tokio::time::sleep(Duration::from_secs(2)).await;
@@ -159,9 +341,167 @@ mod test {
 
         watch_handle.abort();
     }
+
+    pub static EXAMPLE_LIST_3: Artifact<ExampleStruct> = Artifact::new();
+
+    #[tokio::test]
+    async fn test_watch_survives_transient_error() {
+        let tmp_dir = tempdir().unwrap();
+        let artifact_file_path = tmp_dir.path().join("example_watch_resilient.json");
+        std::fs::write(
+            &artifact_file_path,
+            r#"{"field1": "Test string", "field2": 123}"#,
+        )
+            .unwrap();
+
+        EXAMPLE_LIST_3.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+        // Truncate the file to invalid JSON: the next reload should fail but not kill the loop.
+        std::fs::write(&artifact_file_path, "{not valid json").unwrap();
+
+        let watch_handle = tokio::spawn(EXAMPLE_LIST_3.watch(artifact_file_path.to_str().unwrap().to_string(), 1, None));
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(EXAMPLE_LIST_3.watch_status().consecutive_failures() > 0);
+
+        // The last-known-good value should still be served.
+        {
+            let data = EXAMPLE_LIST_3.get().await.unwrap();
+            assert_eq!(data.field2, 123);
+        }
+
+        let new_data = ExampleStruct {
+            field1: "Recovered string".to_string(),
+            field2: 789,
+        };
+        std::fs::write(&artifact_file_path, serde_json::to_string(&new_data).unwrap()).unwrap();
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(EXAMPLE_LIST_3.watch_status().consecutive_failures(), 0);
+        {
+            let data = EXAMPLE_LIST_3.get().await.unwrap();
+            assert_eq!(*data, new_data);
+        }
+
+        watch_handle.abort();
+    }
+
+    pub static EXAMPLE_LIST_4: Artifact<ExampleStruct> = Artifact::new();
+
+    #[tokio::test]
+    async fn test_watch_does_not_rewrite_file_on_reload() {
+        let tmp_dir = tempdir().unwrap();
+        let artifact_file_path = tmp_dir.path().join("example_watch_no_rewrite.json");
+        let original = r#"{"field1":"Test string","field2":123}"#;
+        std::fs::write(&artifact_file_path, original).unwrap();
+
+        EXAMPLE_LIST_4.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+        let watch_handle = tokio::spawn(EXAMPLE_LIST_4.watch(artifact_file_path.to_str().unwrap().to_string(), 1, None));
+
+        // Give the watch task a couple of reload cycles; since the file never changes, it should
+        // never be rewritten (a rewrite would reformat it via persist_data's pretty-printing).
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(std::fs::read_to_string(&artifact_file_path).unwrap(), original);
+
+        // No leftover temp file either: an unchanged reload should never touch persist_data.
+        let tmp_entries: Vec<_> = std::fs::read_dir(tmp_dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".tmp-"))
+            .collect();
+        assert!(tmp_entries.is_empty());
+
+        watch_handle.abort();
+    }
+
+    pub static EXAMPLE_LIST_5: Artifact<ExampleStruct> = Artifact::new();
+
+    #[tokio::test]
+    async fn test_watch_does_not_notify_on_unchanged_reload() {
+        let tmp_dir = tempdir().unwrap();
+        let artifact_file_path = tmp_dir.path().join("example_watch_unchanged.json");
+        std::fs::write(
+            &artifact_file_path,
+            r#"{"field1": "Test string", "field2": 123}"#,
+        )
+            .unwrap();
+
+        EXAMPLE_LIST_5.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+        let changes = EXAMPLE_LIST_5.subscribe();
+        assert!(!changes.has_changed().unwrap());
+
+        let watch_handle = tokio::spawn(EXAMPLE_LIST_5.watch(artifact_file_path.to_str().unwrap().to_string(), 1, None));
+
+        // The file never changes here, so a couple of reload cycles should produce no notification.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!changes.has_changed().unwrap());
+
+        let new_data = ExampleStruct {
+            field1: "Updated string".to_string(),
+            field2: 456,
+        };
+        std::fs::write(&artifact_file_path, serde_json::to_string(&new_data).unwrap()).unwrap();
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(changes.has_changed().unwrap());
+        {
+            let data = EXAMPLE_LIST_5.get().await.unwrap();
+            assert_eq!(*data, new_data);
+        }
+
+        watch_handle.abort();
+    }
+
+    pub static EXAMPLE_LIST_6: Artifact<ExampleStruct> = Artifact::new();
+
+    #[tokio::test]
+    async fn test_watch_migrates_and_rewrites_on_reload() {
+        let tmp_dir = tempdir().unwrap();
+        let artifact_file_path = tmp_dir.path().join("example_watch_migrate.json");
+        std::fs::write(
+            &artifact_file_path,
+            r#"{"field1": "Test string", "field2": 123}"#,
+        )
+            .unwrap();
+
+        EXAMPLE_LIST_6.register_migrations(vec![Box::new(|mut value| {
+            if let Some(name) = value.get_mut("field1").map(|v| v.take()) {
+                value.as_object_mut().unwrap().insert("field1".to_string(), name);
+            }
+            Ok(value)
+        })]);
+        EXAMPLE_LIST_6.init(artifact_file_path.to_str().unwrap()).await.unwrap();
+
+        // Write a version-0 file directly to disk, as if an external process produced it,
+        // rather than going through `update()`.
+        std::fs::write(
+            &artifact_file_path,
+            r#"{"field1": "Externally written", "field2": 999}"#,
+        )
+            .unwrap();
+
+        let watch_handle = tokio::spawn(EXAMPLE_LIST_6.watch(artifact_file_path.to_str().unwrap().to_string(), 1, None));
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        {
+            let data = EXAMPLE_LIST_6.get().await.unwrap();
+            assert_eq!(data.field1, "Externally written");
+            assert_eq!(data.field2, 999);
+        }
+
+        // The reload should have rewritten the file with the bumped version, so the migration
+        // doesn't re-run on every subsequent interval.
+        let contents = std::fs::read_to_string(&artifact_file_path).unwrap();
+        let on_disk: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(on_disk.get("version").and_then(|v| v.as_u64()), Some(1));
+
+        watch_handle.abort();
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ComplexStruct {
     field1: String,
     field2: u32,